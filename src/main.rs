@@ -41,8 +41,14 @@ fn main() {
     let beta = 0.3;
     let gamma = 0.1;
 
-    let mut sir_model = SIRModel::new(graph, time_steps, beta, gamma);
-    sir_model.simulate();
+    let mut sir_model = SIRModel::new(graph, time_steps, beta, gamma)
+        .with_parallel_threshold(500);
+    let trace = sir_model.simulate();
+    println!(
+        "Peak infections at t={:?}, final attack rate {:.2}%",
+        trace.peak_infection_time(),
+        trace.final_attack_rate() * 100.0
+    );
 
     let degree_centrality = sir_model.calculate_degree_centrality();
     let betweenness_centrality = sir_model.calculate_betweenness_centrality();
@@ -56,4 +62,28 @@ fn main() {
     for (node, centrality) in betweenness_centrality {
         println!("Node {}: Betweenness {:.2}", node, centrality);
     }
+
+    let transmission_tree = sir_model.simulate_chain_binomial(7, 0.15);
+    println!("Chain-binomial transmission events (time, sink, source):");
+    for (time, sink, source) in transmission_tree {
+        println!("t={}: Node {} infected by Node {}", time, sink, source);
+    }
+
+    let pagerank = sir_model.calculate_pagerank(0.85, 1e-6, 100);
+    println!("PageRank:");
+    for (node, rank) in pagerank {
+        println!("Node {}: PageRank {:.4}", node, rank);
+    }
+
+    let closeness_centrality = sir_model.calculate_closeness_centrality();
+    println!("Closeness Centrality:");
+    for (node, centrality) in closeness_centrality {
+        println!("Node {}: Closeness {:.4}", node, centrality);
+    }
+
+    let (communities, modularity) = sir_model.detect_communities();
+    println!("Detected communities (modularity {:.4}):", modularity);
+    for (node, community) in communities {
+        println!("Node {}: Community {}", node, community);
+    }
 }
@@ -4,8 +4,18 @@ use petgraph::algo::dijkstra;
 use petgraph::graph::NodeIndex;
 use petgraph::prelude::*;
 use rand::{thread_rng, Rng};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Finalization stack, shortest-path counts, and predecessor lists produced by a
+/// single-source Dijkstra search, as consumed by Brandes' dependency accumulation.
+type ShortestPathState = (Vec<NodeIndex>, HashMap<NodeIndex, f64>, HashMap<NodeIndex, Vec<NodeIndex>>);
+
+/// Below this many nodes, per-source centrality runs serially: spinning up the rayon
+/// thread pool costs more than the Dijkstra searches it would parallelize.
+const DEFAULT_PARALLEL_THRESHOLD: usize = 200;
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum State {
@@ -26,11 +36,65 @@ impl PersonState {
     }
 }
 
+/// Per-timestep record of a `simulate` run, so callers can plot the outbreak over time
+/// instead of only seeing the final compartment states written back to the graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationTrace {
+    /// Number of susceptible nodes at the end of each time step.
+    pub susceptible: Vec<usize>,
+    /// Number of infected nodes at the end of each time step.
+    pub infected: Vec<usize>,
+    /// Number of recovered nodes at the end of each time step.
+    pub recovered: Vec<usize>,
+    /// Number of new infections that occurred during each time step.
+    pub incidence: Vec<usize>,
+    /// `(time, node_id)` pairs for every newly-infected node, in the order infections
+    /// occurred, so the full transmission timeline can be reconstructed.
+    pub new_infections: Vec<(usize, usize)>,
+    /// Number of infected nodes at time zero, before the first step ran.
+    pub initial_infected: usize,
+}
+
+impl SimulationTrace {
+    fn new(initial_infected: usize) -> Self {
+        Self {
+            susceptible: Vec::new(),
+            infected: Vec::new(),
+            recovered: Vec::new(),
+            incidence: Vec::new(),
+            new_infections: Vec::new(),
+            initial_infected,
+        }
+    }
+
+    /// The time step at which the infected count was highest.
+    pub fn peak_infection_time(&self) -> Option<usize> {
+        self.infected
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &count)| count)
+            .map(|(time, _)| time)
+    }
+
+    /// Fraction of the population that was ever infected by the end of the simulation.
+    pub fn final_attack_rate(&self) -> f64 {
+        let population = (self.susceptible.last().copied().unwrap_or(0)
+            + self.infected.last().copied().unwrap_or(0)
+            + self.recovered.last().copied().unwrap_or(0)) as f64;
+        if population == 0.0 {
+            return 0.0;
+        }
+        let ever_infected = self.infected.last().copied().unwrap_or(0) + self.recovered.last().copied().unwrap_or(0);
+        ever_infected as f64 / population
+    }
+}
+
 pub struct SIRModel {
     pub graph: Graph<PersonState, Interaction, Undirected>,
     pub time_steps: usize,
     pub beta: f64,
     pub gamma: f64,
+    pub parallel_threshold: usize,
 }
 
 impl SIRModel {
@@ -40,29 +104,50 @@ impl SIRModel {
             time_steps,
             beta,
             gamma,
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
         }
     }
 
-    pub fn simulate(&mut self) {
+    /// Overrides the node-count threshold above which centrality computations are
+    /// parallelized across a rayon thread pool instead of running serially.
+    pub fn with_parallel_threshold(mut self, parallel_threshold: usize) -> Self {
+        self.parallel_threshold = parallel_threshold;
+        self
+    }
+
+    /// Probability that a single infected neighbor transmits across one edge over the
+    /// whole step: each of the edge's `frequency` contacts is an independent chance to
+    /// transmit, scaled by the interaction `strength`. Passing `strength=1, frequency=1`
+    /// recovers the old flat-`beta` behavior.
+    fn edge_transmission_probability(beta: f64, interaction: &Interaction) -> f64 {
+        1.0 - (1.0 - beta * interaction.strength as f64).powi(interaction.frequency as i32)
+    }
+
+    pub fn simulate(&mut self) -> SimulationTrace {
         let mut rng = thread_rng();
         let mut state_map: HashMap<usize, PersonState> = self.graph.node_indices()
             .map(|idx| (idx.index(), self.graph.node_weight(idx).unwrap().clone()))
             .collect();
 
-        for _ in 0..self.time_steps {
+        let initial_infected = state_map.values().filter(|p| p.state == State::Infected).count();
+        let mut trace = SimulationTrace::new(initial_infected);
+
+        for time in 0..self.time_steps {
             let mut new_state_map = state_map.clone();
+            let mut newly_infected = Vec::new();
             for (node_index, current_state) in state_map.iter() {
                 let node_idx = NodeIndex::new(*node_index);
-                let neighbors = self.graph.neighbors(node_idx);
-                let infected_count = neighbors
-                    .filter(|&n| state_map[&n.index()].state == State::Infected)
-                    .count();
 
                 match current_state.state {
                     State::Susceptible => {
-                        let infection_probability = 1.0 - (1.0 - self.beta).powi(infected_count as i32);
+                        let survival_probability = self.graph.edges(node_idx)
+                            .filter(|edge| state_map[&edge.target().index()].state == State::Infected)
+                            .map(|edge| 1.0 - Self::edge_transmission_probability(self.beta, edge.weight()))
+                            .product::<f64>();
+                        let infection_probability = 1.0 - survival_probability;
                         if rng.gen::<f64>() < infection_probability {
                             new_state_map.get_mut(node_index).unwrap().state = State::Infected;
+                            newly_infected.push(*node_index);
                         }
                     },
                     State::Infected => {
@@ -74,6 +159,12 @@ impl SIRModel {
                 }
             }
             state_map = new_state_map;
+
+            trace.susceptible.push(state_map.values().filter(|p| p.state == State::Susceptible).count());
+            trace.infected.push(state_map.values().filter(|p| p.state == State::Infected).count());
+            trace.recovered.push(state_map.values().filter(|p| p.state == State::Recovered).count());
+            trace.incidence.push(newly_infected.len());
+            trace.new_infections.extend(newly_infected.into_iter().map(|node| (time, node)));
         }
 
         // Assign the final state back to the graph
@@ -82,6 +173,93 @@ impl SIRModel {
                 node.state = state.state;
             }
         }
+
+        trace
+    }
+
+    /// Event-driven alternative to `simulate` using a chain-binomial transmission model
+    /// with an explicit infectious period instead of a flat per-step recovery probability.
+    ///
+    /// Infections are scheduled on a time-ordered queue: when a node becomes infected at
+    /// time `t`, each susceptible neighbor is given one independent Bernoulli trial with
+    /// probability `transmissibility` on each of the `infectious_period` days following
+    /// `t`, and the earliest successful day (if any) schedules that neighbor's infection.
+    /// The node recovers deterministically at `t + infectious_period`. Runs up to
+    /// `self.time_steps`, coexisting with the synchronous `simulate` above.
+    ///
+    /// Returns every infection that took effect as `(time, sink, source)`, in the order
+    /// processed, which together form the transmission tree: events that target a node
+    /// already infected are dropped, so each `sink` appears at most once and its `source`
+    /// is the parent in that tree.
+    pub fn simulate_chain_binomial(&mut self, infectious_period: usize, transmissibility: f64) -> Vec<(usize, usize, usize)> {
+        let mut rng = thread_rng();
+        let mut state_map: HashMap<usize, PersonState> = self.graph.node_indices()
+            .map(|idx| (idx.index(), self.graph.node_weight(idx).unwrap().clone()))
+            .collect();
+        let mut recovery_time: HashMap<usize, usize> = HashMap::new();
+        let mut transmission_tree: Vec<(usize, usize, usize)> = Vec::new();
+
+        // (time, sink_node, source_node), popped in ascending time order.
+        let mut queue: BinaryHeap<Reverse<(usize, usize, usize)>> = BinaryHeap::new();
+
+        for (id, person) in state_map.iter() {
+            if person.state == State::Infected {
+                recovery_time.insert(*id, infectious_period);
+                self.schedule_transmissions(*id, 0, infectious_period, transmissibility, &state_map, &mut queue, &mut rng);
+            }
+        }
+
+        while let Some(Reverse((time, sink, source))) = queue.pop() {
+            if time > self.time_steps || state_map[&sink].state != State::Susceptible {
+                continue;
+            }
+            state_map.get_mut(&sink).unwrap().state = State::Infected;
+            recovery_time.insert(sink, time + infectious_period);
+            transmission_tree.push((time, sink, source));
+            self.schedule_transmissions(sink, time, infectious_period, transmissibility, &state_map, &mut queue, &mut rng);
+        }
+
+        for (id, person) in state_map.iter_mut() {
+            if person.state == State::Infected && recovery_time.get(id).is_some_and(|&t| t <= self.time_steps) {
+                person.state = State::Recovered;
+            }
+        }
+
+        for (id, person) in &state_map {
+            if let Some(node) = self.graph.node_weight_mut(NodeIndex::new(*id)) {
+                node.state = person.state;
+            }
+        }
+
+        transmission_tree
+    }
+
+    /// For every susceptible neighbor of `source`, draws one chain-binomial trial per
+    /// remaining day of the infectious period and schedules an infection event on the
+    /// earliest successful day, if any.
+    #[allow(clippy::too_many_arguments)]
+    fn schedule_transmissions(
+        &self,
+        source: usize,
+        time: usize,
+        infectious_period: usize,
+        transmissibility: f64,
+        state_map: &HashMap<usize, PersonState>,
+        queue: &mut BinaryHeap<Reverse<(usize, usize, usize)>>,
+        rng: &mut impl Rng,
+    ) {
+        for neighbor in self.graph.neighbors(NodeIndex::new(source)) {
+            let sink = neighbor.index();
+            if state_map[&sink].state != State::Susceptible {
+                continue;
+            }
+            for day in 1..=infectious_period {
+                if rng.gen::<f64>() < transmissibility {
+                    queue.push(Reverse((time + day, sink, source)));
+                    break;
+                }
+            }
+        }
     }
 
     pub fn calculate_degree_centrality(&self) -> HashMap<usize, usize> {
@@ -90,38 +268,355 @@ impl SIRModel {
             .collect()
     }
 
+    /// Exact betweenness centrality via Brandes' algorithm, adapted for weighted graphs
+    /// with a Dijkstra-based single-source shortest-path search instead of BFS.
+    ///
+    /// For each source `s`, tracks the number of shortest paths `sigma` and predecessor
+    /// sets `pred` while computing distances, then accumulates pair dependencies `delta`
+    /// in reverse order of finalization. With the binary-heap Dijkstra used here this runs
+    /// in O(V*E log V), on the scaled integer edge weights (`strength * 100`) already used
+    /// elsewhere in this file.
     pub fn calculate_betweenness_centrality(&self) -> HashMap<usize, f64> {
-        let mut centrality = HashMap::new();
-        let node_indices: Vec<_> = self.graph.node_indices().collect();
-    
-        // Iterate over all nodes to calculate shortest paths
-        for node in node_indices.iter() {
-            let shortest_paths = dijkstra(
-                &self.graph, 
-                *node, 
-                None, 
-                |e| (e.weight().strength * 100.0) as u32  // Properly handle float by scaling and converting to u32
-            );
-    
-            // Iterate over all nodes again to check if they appear in the shortest path from the current node
-            for (target, _) in shortest_paths {
-                if node != &target {
-                    *centrality.entry(target.index()).or_insert(0.0) += 1.0;
+        let node_indices: Vec<NodeIndex> = self.graph.node_indices().collect();
+
+        let mut centrality: HashMap<usize, f64> = if node_indices.len() > self.parallel_threshold {
+            node_indices
+                .par_iter()
+                .map(|&source| self.source_contributions(source))
+                .reduce(HashMap::new, Self::merge_contributions)
+        } else {
+            node_indices
+                .iter()
+                .map(|&source| self.source_contributions(source))
+                .fold(HashMap::new(), Self::merge_contributions)
+        };
+
+        for node in self.graph.node_indices() {
+            centrality.entry(node.index()).or_insert(0.0);
+        }
+
+        let n = self.graph.node_count() as f64;
+        let normalization_factor = if n <= 2.0 { 1.0 } else { (n - 1.0) * (n - 2.0) / 2.0 };
+
+        // Each source contributes once per direction of every shortest path, so on an
+        // undirected graph every unordered pair is counted twice (s->t and t->s).
+        for value in centrality.values_mut() {
+            *value /= 2.0 * normalization_factor;
+        }
+
+        centrality
+    }
+
+    /// Dijkstra from `source` that, alongside distances, records the shortest-path count
+    /// `sigma` and predecessor list `pred` for every node, and the order in which nodes
+    /// are finalized (non-decreasing distance).
+    fn single_source_shortest_paths(&self, source: NodeIndex) -> ShortestPathState {
+        let mut dist: HashMap<NodeIndex, u32> = HashMap::new();
+        let mut sigma: HashMap<NodeIndex, f64> = self.graph.node_indices().map(|n| (n, 0.0)).collect();
+        let mut pred: HashMap<NodeIndex, Vec<NodeIndex>> = self.graph.node_indices().map(|n| (n, Vec::new())).collect();
+        let mut stack: Vec<NodeIndex> = Vec::new();
+        let mut visited: HashSet<NodeIndex> = HashSet::new();
+
+        dist.insert(source, 0);
+        sigma.insert(source, 1.0);
+
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0u32, source)));
+
+        while let Some(Reverse((d, v))) = heap.pop() {
+            if !visited.insert(v) {
+                continue;
+            }
+            stack.push(v);
+
+            for edge in self.graph.edges(v) {
+                let w = edge.target();
+                let weight = (edge.weight().strength * 100.0) as u32;
+                let candidate = d + weight;
+
+                match dist.get(&w) {
+                    Some(&existing) if candidate > existing => {}
+                    Some(&existing) if candidate == existing => {
+                        *sigma.get_mut(&w).unwrap() += sigma[&v];
+                        pred.get_mut(&w).unwrap().push(v);
+                    }
+                    _ => {
+                        dist.insert(w, candidate);
+                        sigma.insert(w, sigma[&v]);
+                        pred.insert(w, vec![v]);
+                        heap.push(Reverse((candidate, w)));
+                    }
+                }
+            }
+        }
+
+        (stack, sigma, pred)
+    }
+
+    /// Runs the single-source search from `source` and back-propagates dependencies,
+    /// returning that source's contribution to every other node's betweenness.
+    fn source_contributions(&self, source: NodeIndex) -> HashMap<usize, f64> {
+        let (mut stack, sigma, pred) = self.single_source_shortest_paths(source);
+        let mut delta: HashMap<NodeIndex, f64> = self.graph.node_indices().map(|n| (n, 0.0)).collect();
+        let mut contributions: HashMap<usize, f64> = HashMap::new();
+
+        while let Some(w) = stack.pop() {
+            for &v in &pred[&w] {
+                let contribution = (sigma[&v] / sigma[&w]) * (1.0 + delta[&w]);
+                *delta.get_mut(&v).unwrap() += contribution;
+            }
+            if w != source {
+                *contributions.entry(w.index()).or_insert(0.0) += delta[&w];
+            }
+        }
+
+        contributions
+    }
+
+    /// Folds one source's contribution map into the running total, used as both the
+    /// serial `fold` and parallel `reduce` combinator for betweenness centrality.
+    fn merge_contributions(mut acc: HashMap<usize, f64>, local: HashMap<usize, f64>) -> HashMap<usize, f64> {
+        for (node, value) in local {
+            *acc.entry(node).or_insert(0.0) += value;
+        }
+        acc
+    }
+
+    /// Louvain community detection on the undirected graph, weighted by `Interaction.strength`.
+    ///
+    /// Phase one repeatedly moves each node into the neighboring community that yields
+    /// the largest positive modularity gain until no move helps. Phase two contracts
+    /// each community into a super-node (self-loops carrying intra-community weight,
+    /// inter-community edges summed) and repeats on the aggregated graph. Returns the
+    /// community label for every original node plus the modularity achieved on the
+    /// original graph by that final partition.
+    pub fn detect_communities(&self) -> (HashMap<usize, usize>, f64) {
+        let n = self.graph.node_count();
+        if n == 0 {
+            return (HashMap::new(), 0.0);
+        }
+
+        let mut adjacency: Vec<HashMap<usize, f64>> = vec![HashMap::new(); n];
+        let mut m = 0.0;
+        for edge_idx in self.graph.edge_indices() {
+            let (u, v) = self.graph.edge_endpoints(edge_idx).unwrap();
+            let weight = self.graph[edge_idx].strength as f64;
+            *adjacency[u.index()].entry(v.index()).or_insert(0.0) += weight;
+            *adjacency[v.index()].entry(u.index()).or_insert(0.0) += weight;
+            m += weight;
+        }
+        let degree: Vec<f64> = adjacency.iter().map(|neighbors| neighbors.values().sum()).collect();
+
+        // membership[original_node] tracks which current-level community it belongs to,
+        // updated after every aggregation so it always refers back to original node ids.
+        let mut membership: Vec<usize> = (0..n).collect();
+        let mut level_adjacency = adjacency.clone();
+        let mut level_degree = degree.clone();
+
+        loop {
+            let (community, improved) = Self::louvain_local_moving(&level_adjacency, &level_degree, m);
+            if !improved {
+                break;
+            }
+
+            let mut relabel: HashMap<usize, usize> = HashMap::new();
+            for &c in &community {
+                let next_id = relabel.len();
+                relabel.entry(c).or_insert(next_id);
+            }
+            let community: Vec<usize> = community.iter().map(|c| relabel[c]).collect();
+            let num_communities = relabel.len();
+
+            for slot in membership.iter_mut() {
+                *slot = community[*slot];
+            }
+
+            // A self-loop entry holds twice the intra-community weight (each internal edge is
+            // seen from both endpoints), so a super-node's degree is its plain row sum and
+            // existing self-loops carry over in full on the next aggregation.
+            let mut new_adjacency: Vec<HashMap<usize, f64>> = vec![HashMap::new(); num_communities];
+            for (node, neighbors) in level_adjacency.iter().enumerate() {
+                let c_u = community[node];
+                for (&neighbor, &weight) in neighbors {
+                    let c_v = community[neighbor];
+                    *new_adjacency[c_u].entry(c_v).or_insert(0.0) += weight;
+                }
+            }
+            let new_degree: Vec<f64> = new_adjacency.iter()
+                .map(|neighbors| neighbors.values().sum())
+                .collect();
+
+            let converged = num_communities == level_adjacency.len();
+            level_adjacency = new_adjacency;
+            level_degree = new_degree;
+            if converged {
+                break;
+            }
+        }
+
+        let modularity = Self::modularity(&adjacency, &degree, &membership, m);
+        let labels: HashMap<usize, usize> = membership.into_iter().enumerate().collect();
+
+        (labels, modularity)
+    }
+
+    /// One pass of Louvain's local-moving phase: repeatedly relocates nodes to the
+    /// neighboring community with the largest positive modularity gain until no move
+    /// improves modularity. Returns each node's resulting community id and whether any
+    /// node actually moved.
+    fn louvain_local_moving(adjacency: &[HashMap<usize, f64>], degree: &[f64], m: f64) -> (Vec<usize>, bool) {
+        let n = adjacency.len();
+        let mut community: Vec<usize> = (0..n).collect();
+        if m == 0.0 {
+            return (community, false);
+        }
+
+        let mut sigma_tot: Vec<f64> = degree.to_vec();
+        let mut any_move = false;
+
+        loop {
+            let mut moved = false;
+            for node in 0..n {
+                let current_community = community[node];
+                let k_i = degree[node];
+
+                let mut neighbor_weights: HashMap<usize, f64> = HashMap::new();
+                for (&neighbor, &weight) in &adjacency[node] {
+                    if neighbor != node {
+                        *neighbor_weights.entry(community[neighbor]).or_insert(0.0) += weight;
+                    }
+                }
+
+                sigma_tot[current_community] -= k_i;
+                let k_i_in_current = *neighbor_weights.get(&current_community).unwrap_or(&0.0);
+                let removal_gain = k_i_in_current - sigma_tot[current_community] * k_i / (2.0 * m);
+
+                let mut best_community = current_community;
+                let mut best_gain = 0.0;
+                for (&candidate, &k_i_in) in &neighbor_weights {
+                    if candidate == current_community {
+                        continue;
+                    }
+                    let gain = (k_i_in - sigma_tot[candidate] * k_i / (2.0 * m)) - removal_gain;
+                    if gain > best_gain + 1e-12 {
+                        best_gain = gain;
+                        best_community = candidate;
+                    }
+                }
+
+                sigma_tot[best_community] += k_i;
+                if best_community != current_community {
+                    community[node] = best_community;
+                    moved = true;
+                    any_move = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+
+        (community, any_move)
+    }
+
+    /// Modularity `Q = sum_c [ sigma_in(c)/2m - (sigma_tot(c)/2m)^2 ]` of `membership`
+    /// evaluated against the given weighted adjacency.
+    fn modularity(adjacency: &[HashMap<usize, f64>], degree: &[f64], membership: &[usize], m: f64) -> f64 {
+        if m == 0.0 {
+            return 0.0;
+        }
+
+        let mut internal_weight: HashMap<usize, f64> = HashMap::new();
+        let mut sigma_tot: HashMap<usize, f64> = HashMap::new();
+        for (node, neighbors) in adjacency.iter().enumerate() {
+            *sigma_tot.entry(membership[node]).or_insert(0.0) += degree[node];
+            for (&neighbor, &weight) in neighbors {
+                if membership[neighbor] == membership[node] {
+                    *internal_weight.entry(membership[node]).or_insert(0.0) += weight;
+                }
+            }
+        }
+
+        sigma_tot.iter()
+            .map(|(community, &tot)| {
+                let sigma_in = internal_weight.get(community).copied().unwrap_or(0.0);
+                sigma_in / (2.0 * m) - (tot / (2.0 * m)).powi(2)
+            })
+            .sum()
+    }
+
+    /// PageRank via power iteration, treating each undirected edge as bidirectional and
+    /// weighting transitions by `Interaction.strength`. Starts every node at `1/n`, and
+    /// on each iteration sets `r'[v] = (1-d)/n + d * sum_{u->v} r[u]*w(u,v)/W(u)`, where
+    /// `W(u)` is `u`'s total incident weight; mass from dangling (zero-weight) nodes is
+    /// redistributed uniformly. Stops when the L1 change drops below `tol` or after
+    /// `max_iter` iterations.
+    pub fn calculate_pagerank(&self, damping: f64, tol: f64, max_iter: usize) -> HashMap<usize, f64> {
+        let n = self.graph.node_count();
+        if n == 0 {
+            return HashMap::new();
+        }
+
+        let mut incoming: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+        let mut out_weight = vec![0.0; n];
+        for edge_idx in self.graph.edge_indices() {
+            let (u, v) = self.graph.edge_endpoints(edge_idx).unwrap();
+            let weight = self.graph[edge_idx].strength as f64;
+            incoming[v.index()].push((u.index(), weight));
+            incoming[u.index()].push((v.index(), weight));
+            out_weight[u.index()] += weight;
+            out_weight[v.index()] += weight;
+        }
+
+        let n_f = n as f64;
+        let mut rank = vec![1.0 / n_f; n];
+
+        for _ in 0..max_iter {
+            let dangling_mass: f64 = (0..n).filter(|&u| out_weight[u] == 0.0).map(|u| rank[u]).sum();
+            let base = (1.0 - damping) / n_f + damping * dangling_mass / n_f;
+            let mut new_rank = vec![base; n];
+
+            for v in 0..n {
+                for &(u, weight) in &incoming[v] {
+                    new_rank[v] += damping * rank[u] * weight / out_weight[u];
                 }
             }
+
+            let delta: f64 = new_rank.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+            rank = new_rank;
+            if delta < tol {
+                break;
+            }
         }
-    
+
+        rank.into_iter().enumerate().collect()
+    }
+
+    /// Wasserman-Faust closeness centrality: for each node, `(reachable - 1) / sum(distances)`
+    /// scaled by `(reachable - 1) / (n - 1)` so disconnected components don't inflate the
+    /// score of nodes that can only reach a small fraction of the graph. Uses petgraph's
+    /// Dijkstra with the same `strength * 100` edge weights as `calculate_betweenness_centrality`.
+    pub fn calculate_closeness_centrality(&self) -> HashMap<usize, f64> {
         let n = self.graph.node_count() as f64;
-        let normalization_factor = if n <= 2.0 { 1.0 } else { (n-1.0) * (n-2.0) / 2.0 };
-    
-        // Normalize the betweenness centrality values
-        for value in centrality.values_mut() {
-            *value /= normalization_factor;
+        let mut centrality = HashMap::new();
+
+        for node in self.graph.node_indices() {
+            let distances = dijkstra(&self.graph, node, None, |e| (e.weight().strength * 100.0) as u32);
+            let reachable = distances.len() as f64 - 1.0;
+            let total_distance: f64 = distances.values().map(|&d| d as f64).sum();
+
+            let closeness = if total_distance > 0.0 && n > 1.0 {
+                (reachable / total_distance) * (reachable / (n - 1.0))
+            } else {
+                0.0
+            };
+
+            centrality.insert(node.index(), closeness);
         }
-    
+
         centrality
     }
-}    
+}
 
 
 #[cfg(test)]
@@ -152,4 +647,196 @@ mod tests {
         assert_eq!(sir_model.gamma, 0.1);
     }
 
+    #[test]
+    fn test_betweenness_centrality_path_graph() {
+        // 0 - 1 - 2: every shortest path between the endpoints passes through node 1.
+        let mut graph = Graph::<PersonState, Interaction, Undirected>::new_undirected();
+        let n0 = graph.add_node(PersonState::new(0, State::Susceptible));
+        let n1 = graph.add_node(PersonState::new(1, State::Susceptible));
+        let n2 = graph.add_node(PersonState::new(2, State::Susceptible));
+        graph.add_edge(n0, n1, Interaction { frequency: 1, strength: 1.0 });
+        graph.add_edge(n1, n2, Interaction { frequency: 1, strength: 1.0 });
+
+        let sir_model = SIRModel::new(graph, 1, 0.3, 0.1);
+        let betweenness = sir_model.calculate_betweenness_centrality();
+
+        assert!((betweenness[&0] - 0.0).abs() < f64::EPSILON);
+        assert!((betweenness[&1] - 1.0).abs() < f64::EPSILON);
+        assert!((betweenness[&2] - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_betweenness_centrality_star_graph() {
+        // Center connected to 4 leaves: every leaf-leaf pair's shortest path goes through
+        // the center, so its normalized betweenness is exactly 1.0 and leaves are 0.0.
+        let mut graph = Graph::<PersonState, Interaction, Undirected>::new_undirected();
+        let center = graph.add_node(PersonState::new(0, State::Susceptible));
+        let leaves: Vec<_> = (1..=4)
+            .map(|i| graph.add_node(PersonState::new(i, State::Susceptible)))
+            .collect();
+        for &leaf in &leaves {
+            graph.add_edge(center, leaf, Interaction { frequency: 1, strength: 1.0 });
+        }
+
+        let sir_model = SIRModel::new(graph, 1, 0.3, 0.1);
+        let betweenness = sir_model.calculate_betweenness_centrality();
+
+        assert!((betweenness[&center.index()] - 1.0).abs() < f64::EPSILON);
+        for leaf in leaves {
+            assert!((betweenness[&leaf.index()] - 0.0).abs() < f64::EPSILON);
+        }
+    }
+
+    fn setup_fixed_weighted_graph() -> Graph<PersonState, Interaction, Undirected> {
+        // A 6-node cycle with varying strengths, so shortest paths aren't all symmetric.
+        let mut graph = Graph::<PersonState, Interaction, Undirected>::new_undirected();
+        let nodes: Vec<_> = (0..6)
+            .map(|i| graph.add_node(PersonState::new(i, State::Susceptible)))
+            .collect();
+        let strengths = [0.9, 0.3, 0.6, 1.0, 0.4, 0.7];
+        for i in 0..nodes.len() {
+            let j = (i + 1) % nodes.len();
+            graph.add_edge(nodes[i], nodes[j], Interaction { frequency: 3, strength: strengths[i] });
+        }
+        graph
+    }
+
+    #[test]
+    fn test_betweenness_centrality_serial_matches_parallel() {
+        let serial_model = SIRModel::new(setup_fixed_weighted_graph(), 1, 0.3, 0.1)
+            .with_parallel_threshold(usize::MAX);
+        let parallel_model = SIRModel::new(setup_fixed_weighted_graph(), 1, 0.3, 0.1)
+            .with_parallel_threshold(0);
+
+        let serial = serial_model.calculate_betweenness_centrality();
+        let parallel = parallel_model.calculate_betweenness_centrality();
+
+        assert_eq!(serial.len(), parallel.len());
+        for (node, value) in &serial {
+            assert_eq!(*value, parallel[node]);
+        }
+    }
+
+    #[test]
+    fn test_simulate_chain_binomial_guaranteed_transmission() {
+        // transmissibility = 1.0 guarantees node 1 is infected on the first day. With
+        // time_steps=3, node 0 (infected at t=0) has just recovered by t=3, while node 1
+        // (infected at t=1) won't recover until t=4, so it should still be Infected.
+        let mut graph = Graph::<PersonState, Interaction, Undirected>::new_undirected();
+        let node0 = graph.add_node(PersonState::new(0, State::Infected));
+        let node1 = graph.add_node(PersonState::new(1, State::Susceptible));
+        graph.add_edge(node0, node1, Interaction { frequency: 1, strength: 1.0 });
+
+        let mut sir_model = SIRModel::new(graph, 3, 0.3, 0.1);
+        let transmission_tree = sir_model.simulate_chain_binomial(3, 1.0);
+
+        let node0_state = sir_model.graph.node_weight(node0).unwrap().state;
+        let node1_state = sir_model.graph.node_weight(node1).unwrap().state;
+        assert_eq!(node0_state, State::Recovered);
+        assert_eq!(node1_state, State::Infected);
+
+        assert_eq!(transmission_tree, vec![(1, node1.index(), node0.index())]);
+    }
+
+    #[test]
+    fn test_simulate_trace_conserves_population_and_incidence() {
+        let mut graph = Graph::<PersonState, Interaction, Undirected>::new_undirected();
+        let nodes: Vec<_> = (0..10)
+            .map(|i| graph.add_node(PersonState::new(i, if i < 2 { State::Infected } else { State::Susceptible })))
+            .collect();
+        for i in 0..nodes.len() {
+            for j in (i + 1)..nodes.len() {
+                graph.add_edge(nodes[i], nodes[j], Interaction { frequency: 2, strength: 0.5 });
+            }
+        }
+        let population = nodes.len();
+
+        let mut sir_model = SIRModel::new(graph, 15, 0.2, 0.3);
+        let trace = sir_model.simulate();
+
+        for t in 0..trace.susceptible.len() {
+            assert_eq!(trace.susceptible[t] + trace.infected[t] + trace.recovered[t], population);
+        }
+
+        let total_incidence: usize = trace.incidence.iter().sum();
+        let final_ever_infected = trace.recovered.last().unwrap() + trace.infected.last().unwrap();
+        assert_eq!(total_incidence, final_ever_infected - trace.initial_infected);
+        assert_eq!(total_incidence, trace.new_infections.len());
+    }
+
+    #[test]
+    fn test_edge_transmission_probability_scales_with_strength_and_frequency() {
+        let strong = Interaction { frequency: 10, strength: 1.0 };
+        let weak = Interaction { frequency: 1, strength: 0.05 };
+
+        let strong_probability = SIRModel::edge_transmission_probability(0.5, &strong);
+        let weak_probability = SIRModel::edge_transmission_probability(0.5, &weak);
+
+        assert!(strong_probability > 0.999);
+        assert!(weak_probability < 0.05);
+        assert!(strong_probability > weak_probability);
+    }
+
+    #[test]
+    fn test_detect_communities_two_cliques_joined_by_one_edge() {
+        // Two strongly-connected triangles joined by a single weak bridge edge should
+        // resolve into exactly two communities, split along the bridge.
+        let mut graph = Graph::<PersonState, Interaction, Undirected>::new_undirected();
+        let clique_a: Vec<_> = (0..3).map(|i| graph.add_node(PersonState::new(i, State::Susceptible))).collect();
+        let clique_b: Vec<_> = (3..6).map(|i| graph.add_node(PersonState::new(i, State::Susceptible))).collect();
+
+        for i in 0..clique_a.len() {
+            for j in (i + 1)..clique_a.len() {
+                graph.add_edge(clique_a[i], clique_a[j], Interaction { frequency: 1, strength: 1.0 });
+            }
+        }
+        for i in 0..clique_b.len() {
+            for j in (i + 1)..clique_b.len() {
+                graph.add_edge(clique_b[i], clique_b[j], Interaction { frequency: 1, strength: 1.0 });
+            }
+        }
+        graph.add_edge(clique_a[0], clique_b[0], Interaction { frequency: 1, strength: 0.1 });
+
+        let sir_model = SIRModel::new(graph, 1, 0.3, 0.1);
+        let (communities, modularity) = sir_model.detect_communities();
+
+        let community_a = communities[&clique_a[0].index()];
+        let community_b = communities[&clique_b[0].index()];
+        assert_ne!(community_a, community_b);
+        for node in &clique_a {
+            assert_eq!(communities[&node.index()], community_a);
+        }
+        for node in &clique_b {
+            assert_eq!(communities[&node.index()], community_b);
+        }
+        assert!(modularity > 0.0);
+    }
+
+    #[test]
+    fn test_pagerank_and_closeness_star_graph_center_dominates() {
+        let mut graph = Graph::<PersonState, Interaction, Undirected>::new_undirected();
+        let center = graph.add_node(PersonState::new(0, State::Susceptible));
+        let leaves: Vec<_> = (1..=4)
+            .map(|i| graph.add_node(PersonState::new(i, State::Susceptible)))
+            .collect();
+        for &leaf in &leaves {
+            graph.add_edge(center, leaf, Interaction { frequency: 1, strength: 1.0 });
+        }
+
+        let sir_model = SIRModel::new(graph, 1, 0.3, 0.1);
+
+        let pagerank = sir_model.calculate_pagerank(0.85, 1e-9, 100);
+        let center_rank = pagerank[&center.index()];
+        for leaf in &leaves {
+            assert!(center_rank > pagerank[&leaf.index()]);
+        }
+        let total_rank: f64 = pagerank.values().sum();
+        assert!((total_rank - 1.0).abs() < 1e-6);
+
+        let closeness = sir_model.calculate_closeness_centrality();
+        let center_closeness = closeness[&center.index()];
+        for leaf in &leaves {
+            assert!(center_closeness > closeness[&leaf.index()]);
+        }
+    }
 }